@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use serde::{de::Error as DeError, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{Map, Value};
 use std::env;
 
@@ -23,15 +23,16 @@ pub struct BootstrapRequest {
     pub rel_labels: Vec<String>,
 }
 
-#[derive(Serialize, Debug)]
+// `Relation` is declared before `Node` so an untagged deserialize tries it
+// first: its required `startId`/`endId` make a `Node` payload fail that
+// variant and fall through, while a `Relation` payload matches immediately.
+// This disambiguates the two variants without `deny_unknown_fields`, which
+// would also reject a forward-compatible payload carrying a field this SDK
+// doesn't know about yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum SourceElement {
-    Node {
-        id: String,
-        labels: Vec<String>,
-        properties: Map<String, Value>,
-    },
     Relation {
         id: String,
         labels: Vec<String>,
@@ -41,29 +42,107 @@ pub enum SourceElement {
         #[serde(rename = "endId")]
         end_id: String,
     },
+    Node {
+        id: String,
+        labels: Vec<String>,
+        properties: Map<String, Value>,
+    },
+}
+
+impl SourceElement {
+    fn matches_bootstrap(&self, request: &BootstrapRequest) -> bool {
+        match self {
+            SourceElement::Node { labels, .. } => {
+                labels.iter().any(|l| request.node_labels.contains(l))
+            }
+            SourceElement::Relation { labels, .. } => {
+                labels.iter().any(|l| request.rel_labels.contains(l))
+            }
+        }
+    }
+
+    fn id(&self) -> &str {
+        match self {
+            SourceElement::Node { id, .. } => id,
+            SourceElement::Relation { id, .. } => id,
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            SourceElement::Node { .. } => "node",
+            SourceElement::Relation { .. } => "rel",
+        }
+    }
 }
 
-#[derive(Serialize, Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ChangeOp {
-    #[serde(rename = "i")]
     Create,
-
-    #[serde(rename = "u")]
     Update,
-
-    #[serde(rename = "d")]
     Delete,
+    Read,
+    /// Any op code not recognized by this version of the SDK. Preserves the
+    /// original string so forwarding/logging code keeps working and so a
+    /// round trip through `Serialize`/`Deserialize` is lossless.
+    Unknown(String),
+}
+
+impl Serialize for ChangeOp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(match self {
+            ChangeOp::Create => "i",
+            ChangeOp::Update => "u",
+            ChangeOp::Delete => "d",
+            ChangeOp::Read => "r",
+            ChangeOp::Unknown(op) => op,
+        })
+    }
 }
 
-#[derive(Debug)]
+impl<'de> Deserialize<'de> for ChangeOp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let op = String::deserialize(deserializer)?;
+        Ok(match op.as_str() {
+            "i" => ChangeOp::Create,
+            "u" => ChangeOp::Update,
+            "d" => ChangeOp::Delete,
+            "r" => ChangeOp::Read,
+            _ => ChangeOp::Unknown(op),
+        })
+    }
+}
+
+const TRACEPARENT_KEY: &str = "traceparent";
+const TRACESTATE_KEY: &str = "tracestate";
+
+/// A W3C trace-context carrier (<https://www.w3.org/TR/trace-context/>)
+/// propagated through `SourceChange::metadata` so a change can be
+/// correlated into the same OTEL trace as it moves from source to query to
+/// reaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceContext {
+    pub traceparent: String,
+    pub tracestate: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
 pub struct SourceChange {
     op: ChangeOp,
     element: SourceElement,
+    previous: Option<SourceElement>,
     metadata: Option<Map<String, Value>>,
     reactivator_start_ns: u128,
     reactivator_end_ns: u128,
     source_ns: u128,
     seq: u64,
+    tombstone: bool,
 }
 
 impl SourceChange {
@@ -78,17 +157,113 @@ impl SourceChange {
         SourceChange {
             op,
             element,
+            previous: None,
             metadata,
             reactivator_start_ns,
             reactivator_end_ns: 0,
             source_ns,
             seq,
+            tombstone: false,
         }
     }
 
+    /// Builds a `Delete` change, and, when `emit_tombstone` is set, a
+    /// companion tombstone change to follow it. On log-compacted transports
+    /// (e.g. Kafka) the tombstone's null-valued record lets compaction
+    /// reclaim the deleted key; transports without compaction can pass
+    /// `false` to skip it.
+    pub fn new_delete(
+        element: SourceElement,
+        reactivator_start_ns: u128,
+        source_ns: u128,
+        seq: u64,
+        metadata: Option<Map<String, Value>>,
+        emit_tombstone: bool,
+    ) -> (SourceChange, Option<SourceChange>) {
+        let tombstone = emit_tombstone.then(|| SourceChange {
+            op: ChangeOp::Delete,
+            element: element.clone(),
+            previous: None,
+            metadata: None,
+            reactivator_start_ns,
+            reactivator_end_ns: 0,
+            source_ns,
+            seq,
+            tombstone: true,
+        });
+        let change = SourceChange::new(ChangeOp::Delete, element, reactivator_start_ns, source_ns, seq, metadata);
+        (change, tombstone)
+    }
+
     pub fn set_reactivator_end_ns(&mut self, reactivator_end_ns: u128) {
         self.reactivator_end_ns = reactivator_end_ns;
     }
+
+    pub fn set_previous(&mut self, previous: SourceElement) {
+        self.previous = Some(previous);
+    }
+
+    /// Injects a trace context into `metadata` so the next hop can continue
+    /// the same OTEL trace.
+    pub fn set_trace_context(&mut self, trace_context: TraceContext) {
+        let metadata = self.metadata.get_or_insert_with(Map::new);
+        metadata.insert(
+            TRACEPARENT_KEY.to_string(),
+            Value::String(trace_context.traceparent),
+        );
+        match trace_context.tracestate {
+            Some(tracestate) => {
+                metadata.insert(TRACESTATE_KEY.to_string(), Value::String(tracestate));
+            }
+            None => {
+                metadata.remove(TRACESTATE_KEY);
+            }
+        }
+    }
+
+    /// Extracts the trace context previously injected by
+    /// [`SourceChange::set_trace_context`], if any.
+    pub fn trace_context(&self) -> Option<TraceContext> {
+        let metadata = self.metadata.as_ref()?;
+        let traceparent = metadata.get(TRACEPARENT_KEY)?.as_str()?.to_string();
+        let tracestate = metadata
+            .get(TRACESTATE_KEY)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        Some(TraceContext {
+            traceparent,
+            tracestate,
+        })
+    }
+
+    /// Builds a stream of `Read` changes for the elements of a snapshot that
+    /// match a `BootstrapRequest`'s node/rel label filters. `watermark_ns`
+    /// and `watermark_seq` are stamped on every change so a consumer can
+    /// detect when the snapshot ends and streaming changes resume from the
+    /// same position.
+    pub fn from_bootstrap<'a, I>(
+        request: &'a BootstrapRequest,
+        elements: I,
+        watermark_ns: u128,
+        watermark_seq: u64,
+    ) -> impl Iterator<Item = SourceChange> + 'a
+    where
+        I: IntoIterator<Item = SourceElement> + 'a,
+    {
+        elements
+            .into_iter()
+            .filter(move |element| element.matches_bootstrap(request))
+            .map(move |element| {
+                SourceChange::new(
+                    ChangeOp::Read,
+                    element,
+                    watermark_ns,
+                    watermark_ns,
+                    watermark_seq,
+                    None,
+                )
+            })
+    }
 }
 
 struct SourceData<'a>(&'a SourceChange);
@@ -101,23 +276,7 @@ impl<'a> Serialize for SourceData<'a> {
         let mut state = serializer.serialize_struct("SourceData", 1)?;
         state.serialize_field("db", &env::var("SOURCE_ID").unwrap_or("drasi".to_string()))?;
         state.serialize_field("lsn", &self.0.seq)?;
-        state.serialize_field(
-            "table",
-            match &self.0.element {
-                SourceElement::Node {
-                    id: _,
-                    labels: _,
-                    properties: _,
-                } => "node",
-                SourceElement::Relation {
-                    id: _,
-                    labels: _,
-                    properties: _,
-                    start_id: _,
-                    end_id: _,
-                } => "rel",
-            },
-        )?;
+        state.serialize_field("table", self.0.element.table())?;
         state.serialize_field("ts_ns", &self.0.source_ns)?;
         state.end()
     }
@@ -130,25 +289,50 @@ impl<'a> Serialize for Payload<'a> {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Payload", 2)?;
-        state.serialize_field(
-            match &self.0.op {
-                ChangeOp::Create => "after",
-                ChangeOp::Update => "after",
-                ChangeOp::Delete => "before",
-            },
-            &self.0.element,
-        )?;
+        let mut state = serializer.serialize_struct("Payload", 3)?;
+        match &self.0.op {
+            ChangeOp::Create | ChangeOp::Read | ChangeOp::Unknown(_) => {
+                state.serialize_field("after", &self.0.element)?;
+            }
+            ChangeOp::Update => {
+                state.serialize_field("before", &self.0.previous)?;
+                state.serialize_field("after", &self.0.element)?;
+            }
+            ChangeOp::Delete => {
+                state.serialize_field("before", &self.0.element)?;
+            }
+        }
         state.serialize_field("source", &SourceData(self.0))?;
         state.end()
     }
 }
 
+struct TombstoneSource<'a>(&'a SourceElement);
+
+impl<'a> Serialize for TombstoneSource<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("TombstoneSource", 1)?;
+        state.serialize_field("table", self.0.table())?;
+        state.end()
+    }
+}
+
 impl Serialize for SourceChange {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
+        if self.tombstone {
+            let mut state = serializer.serialize_struct("SourceChange", 3)?;
+            state.serialize_field("id", self.element.id())?;
+            state.serialize_field("source", &TombstoneSource(&self.element))?;
+            state.serialize_field("payload", &Value::Null)?;
+            return state.end();
+        }
+
         let mut state = serializer.serialize_struct("SourceChange", 4)?;
         state.serialize_field("op", &self.op)?;
         state.serialize_field("payload", &Payload(self))?;
@@ -161,6 +345,60 @@ impl Serialize for SourceChange {
     }
 }
 
+#[derive(Deserialize)]
+struct RawSourceData {
+    lsn: u64,
+    ts_ns: u128,
+}
+
+#[derive(Deserialize)]
+struct RawPayload {
+    before: Option<SourceElement>,
+    after: Option<SourceElement>,
+    source: RawSourceData,
+}
+
+#[derive(Deserialize)]
+struct RawSourceChange {
+    op: ChangeOp,
+    payload: RawPayload,
+    #[serde(rename = "reactivatorStart_ns")]
+    reactivator_start_ns: u128,
+    #[serde(rename = "reactivatorEnd_ns")]
+    reactivator_end_ns: u128,
+    metadata: Option<Map<String, Value>>,
+}
+
+impl<'de> Deserialize<'de> for SourceChange {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut raw = RawSourceChange::deserialize(deserializer)?;
+        let previous = match raw.op {
+            ChangeOp::Update => raw.payload.before.take(),
+            _ => None,
+        };
+        let element = match raw.op {
+            ChangeOp::Delete => raw.payload.before.take(),
+            _ => raw.payload.after.take(),
+        }
+        .ok_or_else(|| D::Error::custom("SourceChange payload is missing the element"))?;
+
+        Ok(SourceChange {
+            op: raw.op,
+            element,
+            previous,
+            metadata: raw.metadata,
+            reactivator_start_ns: raw.reactivator_start_ns,
+            reactivator_end_ns: raw.reactivator_end_ns,
+            source_ns: raw.payload.source.ts_ns,
+            seq: raw.payload.source.lsn,
+            tombstone: false,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,6 +519,7 @@ mod tests {
         let expected = json!({
             "op": "u",
             "payload": {
+                "before": null,
                 "after": {
                     "id": "1",
                     "labels": ["Person"],
@@ -306,6 +545,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serialize_node_update_with_previous() {
+        let previous = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: vec![("field1".to_string(), Value::String("foo".to_string()))]
+                .into_iter()
+                .collect(),
+        };
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: vec![("field1".to_string(), Value::String("baz".to_string()))]
+                .into_iter()
+                .collect(),
+        };
+        let mut change = SourceChange::new(ChangeOp::Update, node, 1234567890000000000, 1234500000123456789, 1, None);
+        change.set_previous(previous);
+        let current_time = 1234567890001234567;
+        change.set_reactivator_end_ns(current_time);
+        let serialized = serde_json::to_string(&change).unwrap();
+        let expected = json!({
+            "op": "u",
+            "payload": {
+                "before": {
+                    "id": "1",
+                    "labels": ["Person"],
+                    "properties": {
+                        "field1": "foo",
+                    },
+                },
+                "after": {
+                    "id": "1",
+                    "labels": ["Person"],
+                    "properties": {
+                        "field1": "baz",
+                    },
+                },
+                "source": {
+                    "db": "drasi",
+                    "lsn": 1,
+                    "table": "node",
+                    "ts_ns": 1234500000123456789u128,
+                },
+            },
+            "reactivatorEnd_ns": current_time,
+            "reactivatorStart_ns": 1234567890000000000u128,
+        });
+        assert_eq!(
+            serde_json::from_str::<Value>(&serialized).unwrap(),
+            expected
+        );
+    }
+
     #[test]
     fn test_serialize_node_delete() {
         let node = SourceElement::Node {
@@ -350,4 +643,289 @@ mod tests {
             expected
         );
     }
+
+    #[test]
+    fn test_from_bootstrap_filters_by_label_and_stamps_watermark() {
+        let request = BootstrapRequest {
+            node_labels: vec!["Person".to_string()],
+            rel_labels: vec![],
+        };
+        let person = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: Map::new(),
+        };
+        let car = SourceElement::Node {
+            id: "2".to_string(),
+            labels: vec!["Car".to_string()],
+            properties: Map::new(),
+        };
+        let changes: Vec<SourceChange> =
+            SourceChange::from_bootstrap(&request, vec![person, car], 1234500000123456789, 7)
+                .collect();
+
+        assert_eq!(changes.len(), 1);
+        let serialized = serde_json::to_string(&changes[0]).unwrap();
+        let expected = json!({
+            "op": "r",
+            "payload": {
+                "after": {
+                    "id": "1",
+                    "labels": ["Person"],
+                    "properties": {},
+                },
+                "source": {
+                    "db": "drasi",
+                    "lsn": 7,
+                    "table": "node",
+                    "ts_ns": 1234500000123456789u128,
+                },
+            },
+            "reactivatorStart_ns": 1234500000123456789u128,
+            "reactivatorEnd_ns": 0,
+        });
+        assert_eq!(
+            serde_json::from_str::<Value>(&serialized).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_from_bootstrap_filters_relation_by_rel_labels() {
+        let request = BootstrapRequest {
+            node_labels: vec![],
+            rel_labels: vec!["KNOWS".to_string()],
+        };
+        let knows = SourceElement::Relation {
+            id: "1".to_string(),
+            labels: vec!["KNOWS".to_string()],
+            properties: Map::new(),
+            start_id: "2".to_string(),
+            end_id: "3".to_string(),
+        };
+        let likes = SourceElement::Relation {
+            id: "2".to_string(),
+            labels: vec!["LIKES".to_string()],
+            properties: Map::new(),
+            start_id: "2".to_string(),
+            end_id: "3".to_string(),
+        };
+        let changes: Vec<SourceChange> =
+            SourceChange::from_bootstrap(&request, vec![knows, likes], 1234500000123456789, 7)
+                .collect();
+
+        assert_eq!(changes.len(), 1);
+        let serialized = serde_json::to_string(&changes[0]).unwrap();
+        let expected = json!({
+            "op": "r",
+            "payload": {
+                "after": {
+                    "id": "1",
+                    "labels": ["KNOWS"],
+                    "properties": {},
+                    "startId": "2",
+                    "endId": "3",
+                },
+                "source": {
+                    "db": "drasi",
+                    "lsn": 7,
+                    "table": "rel",
+                    "ts_ns": 1234500000123456789u128,
+                },
+            },
+            "reactivatorStart_ns": 1234500000123456789u128,
+            "reactivatorEnd_ns": 0,
+        });
+        assert_eq!(
+            serde_json::from_str::<Value>(&serialized).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_round_trip_node_insert() {
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: vec![("field1".to_string(), Value::String("foo".to_string()))]
+                .into_iter()
+                .collect(),
+        };
+        let change = SourceChange::new(ChangeOp::Create, node, 1234567890000000000, 1234500000123456789, 1, None);
+        let serialized = serde_json::to_string(&change).unwrap();
+        let deserialized: SourceChange = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, change);
+    }
+
+    #[test]
+    fn test_round_trip_relation_delete() {
+        let relation = SourceElement::Relation {
+            id: "1".to_string(),
+            labels: vec!["KNOWS".to_string()],
+            properties: Map::new(),
+            start_id: "2".to_string(),
+            end_id: "3".to_string(),
+        };
+        let change = SourceChange::new(ChangeOp::Delete, relation, 1234567890000000000, 1234500000123456789, 1, None);
+        let serialized = serde_json::to_string(&change).unwrap();
+        let deserialized: SourceChange = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, change);
+    }
+
+    #[test]
+    fn test_round_trip_read() {
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: Map::new(),
+        };
+        let change = SourceChange::new(ChangeOp::Read, node, 1234500000123456789, 1234500000123456789, 7, None);
+        let serialized = serde_json::to_string(&change).unwrap();
+        let deserialized: SourceChange = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, change);
+    }
+
+    #[test]
+    fn test_round_trip_node_update_with_previous() {
+        let previous = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: vec![("field1".to_string(), Value::String("foo".to_string()))]
+                .into_iter()
+                .collect(),
+        };
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: vec![("field1".to_string(), Value::String("baz".to_string()))]
+                .into_iter()
+                .collect(),
+        };
+        let mut change = SourceChange::new(ChangeOp::Update, node, 1234567890000000000, 1234500000123456789, 1, None);
+        change.set_previous(previous);
+        let serialized = serde_json::to_string(&change).unwrap();
+        let deserialized: SourceChange = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, change);
+    }
+
+    #[test]
+    fn test_round_trip_node_delete() {
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: Map::new(),
+        };
+        let change = SourceChange::new(ChangeOp::Delete, node, 1234567890000000000, 1234500000123456789, 1, None);
+        let serialized = serde_json::to_string(&change).unwrap();
+        let deserialized: SourceChange = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, change);
+    }
+
+    #[test]
+    fn test_round_trip_unknown_op_preserves_original_code() {
+        let json_str = r#"{
+            "op": "t",
+            "payload": {
+                "after": {
+                    "id": "1",
+                    "labels": ["Person"],
+                    "properties": {}
+                },
+                "source": {
+                    "db": "drasi",
+                    "lsn": 1,
+                    "table": "node",
+                    "ts_ns": 1234500000123456789
+                }
+            },
+            "reactivatorStart_ns": 1234567890000000000,
+            "reactivatorEnd_ns": 1234567890001234567
+        }"#;
+        let change: SourceChange = serde_json::from_str(json_str).unwrap();
+        assert_eq!(change.op, ChangeOp::Unknown("t".to_string()));
+        let serialized = serde_json::to_string(&change).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&serialized).unwrap()["op"],
+            json!("t")
+        );
+    }
+
+    #[test]
+    fn test_trace_context_round_trips_through_metadata() {
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: Map::new(),
+        };
+        let mut change = SourceChange::new(ChangeOp::Create, node, 1234567890000000000, 1234500000123456789, 1, None);
+        change.set_trace_context(TraceContext {
+            traceparent: "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+            tracestate: Some("congo=t61rcWkgMzE".to_string()),
+        });
+
+        let serialized = serde_json::to_string(&change).unwrap();
+        let deserialized: SourceChange = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(
+            deserialized.trace_context(),
+            Some(TraceContext {
+                traceparent: "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+                tracestate: Some("congo=t61rcWkgMzE".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_trace_context_absent_when_not_set() {
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: Map::new(),
+        };
+        let change = SourceChange::new(ChangeOp::Create, node, 1234567890000000000, 1234500000123456789, 1, None);
+        assert_eq!(change.trace_context(), None);
+    }
+
+    #[test]
+    fn test_new_delete_emits_tombstone_when_enabled() {
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: Map::new(),
+        };
+        let (change, tombstone) =
+            SourceChange::new_delete(node, 1234567890000000000, 1234500000123456789, 1, None, true);
+
+        let serialized = serde_json::to_string(&change).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&serialized).unwrap()["op"],
+            json!("d")
+        );
+
+        let tombstone = tombstone.expect("tombstone should be emitted when enabled");
+        let serialized = serde_json::to_string(&tombstone).unwrap();
+        let expected = json!({
+            "id": "1",
+            "source": {
+                "table": "node",
+            },
+            "payload": null,
+        });
+        assert_eq!(
+            serde_json::from_str::<Value>(&serialized).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_new_delete_skips_tombstone_when_disabled() {
+        let node = SourceElement::Node {
+            id: "1".to_string(),
+            labels: vec!["Person".to_string()],
+            properties: Map::new(),
+        };
+        let (_, tombstone) =
+            SourceChange::new_delete(node, 1234567890000000000, 1234500000123456789, 1, None, false);
+        assert!(tombstone.is_none());
+    }
 }